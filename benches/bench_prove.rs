@@ -3,8 +3,10 @@ use sigmabus_poc::transcript::{tests::poseidon_test_config, PoseidonTranscript};
 
 use criterion::{criterion_group, criterion_main, Criterion};
 use ark_bn254::{Bn254, Fr, G1Projective};
+use ark_ec::Group;
 use ark_std::rand::{RngCore, SeedableRng};
 use ark_std::test_rng;
+use sigmabus_poc::commitment::PoseidonCommitment;
 use sigmabus_poc::sigmabus::Sigmabus;
 
 fn bench_prove(c: &mut Criterion) {
@@ -12,25 +14,73 @@ fn bench_prove(c: &mut Criterion) {
     let poseidon_config = poseidon_test_config::<Fr>();
 
     // generate the trusted setup
-    let params = Sigmabus::<Bn254>::setup(&mut rng, &poseidon_config);
+    let params = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::setup(
+        &mut rng,
+        &poseidon_config,
+        &poseidon_config,
+    );
 
     // compute the witness x
     let x = Fr::rand(&mut rng);
+    let G = G1Projective::generator();
 
     let mut transcript_p = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
 
     // generate Sigmabus proof for X==x*G
     c.bench_function("prove", |b| {
         b.iter(|| {
-            let _proof = Sigmabus::<Bn254>::prove(&mut rng, &params, &mut transcript_p, x).unwrap();
+            let _proof =
+                Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::prove(
+                    &mut rng,
+                    &params,
+                    &mut transcript_p,
+                    G,
+                    x,
+                )
+                .unwrap();
         });
     });
 }
 
+// bench_prove_batch measures the time to prove n instances in a single Groth16 proof (via
+// Sigmabus::prove_batch); dividing it by n gives the per-proof cost to set against bench_prove.
+fn bench_prove_batch(c: &mut Criterion) {
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let poseidon_config = poseidon_test_config::<Fr>();
+
+    let mut group = c.benchmark_group("prove_batch");
+    for n in [2, 4, 8, 16] {
+        let params = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::setup_batch(
+            &mut rng,
+            &poseidon_config,
+            &poseidon_config,
+            n,
+        );
+
+        let xs: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+
+        let mut transcript_p = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+
+        group.bench_function(format!("n={n}"), |b| {
+            b.iter(|| {
+                let _proof = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::prove_batch(
+                    &mut rng,
+                    &params,
+                    &mut transcript_p,
+                    &xs,
+                )
+                .unwrap();
+            });
+        });
+    }
+    group.finish();
+}
+
 criterion_group! {
     name=prover_benches;
     config=Criterion::default();
     targets=
             bench_prove,
+            bench_prove_batch,
 }
 criterion_main!(prover_benches);
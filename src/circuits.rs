@@ -8,15 +8,22 @@ use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 use core::marker::PhantomData;
 
+use crate::commitment::CommitmentScheme;
+use crate::transcript::PoseidonTranscriptVar;
+
 // CF (ConstraintField)
 pub type CF<C> = <<C as CurveGroup>::Affine as AffineRepr>::ScalarField;
 
 #[derive(Debug, Clone)]
-pub struct GenZKCircuit<C: CurveGroup> {
+pub struct GenZKCircuit<C: CurveGroup, CS: CommitmentScheme<C>>
+where
+    C::ScalarField: Absorb,
+{
     pub _c: PhantomData<C>,
     pub poseidon_config: PoseidonConfig<C::ScalarField>,
+    pub commitment_params: CS::Params,
     // public
-    pub cm: C::ScalarField,
+    pub cm: CS::Commitment,
     pub s: C::ScalarField,
     pub r_h: C::ScalarField,
     pub c: C::ScalarField,
@@ -25,13 +32,13 @@ pub struct GenZKCircuit<C: CurveGroup> {
     pub r: C::ScalarField,
     pub o_h: C::ScalarField,
 }
-impl<C: CurveGroup> ConstraintSynthesizer<CF<C>> for GenZKCircuit<C>
+impl<C: CurveGroup, CS: CommitmentScheme<C>> ConstraintSynthesizer<CF<C>> for GenZKCircuit<C, CS>
 where
     C::ScalarField: Absorb,
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<CF<C>>) -> Result<(), SynthesisError> {
         // public inputs
-        let cmVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.cm))?;
+        let cmVar = CS::alloc_commitment(cs.clone(), &self.cm)?;
         let sVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.s))?;
         let r_hVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.r_h))?;
         let cVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.c))?;
@@ -48,21 +55,34 @@ where
             )
             .unwrap();
 
-        Self::check(&crh_params, cmVar, sVar, r_hVar, cVar, xVar, rVar, o_hVar)?;
+        Self::check(
+            &self.commitment_params,
+            &crh_params,
+            cs,
+            cmVar,
+            sVar,
+            r_hVar,
+            cVar,
+            xVar,
+            rVar,
+            o_hVar,
+        )?;
 
         Ok(())
     }
 }
 
-impl<C: CurveGroup> GenZKCircuit<C>
+impl<C: CurveGroup, CS: CommitmentScheme<C>> GenZKCircuit<C, CS>
 where
     C::ScalarField: Absorb,
 {
     #[allow(clippy::too_many_arguments)]
     pub fn check(
+        commitment_params: &CS::Params,
         crh_params: &CRHParametersVar<C::ScalarField>,
+        cs: ConstraintSystemRef<CF<C>>,
         // public inputs:
-        cm: FpVar<C::ScalarField>,
+        cm: CS::CommitmentVar,
         s: FpVar<C::ScalarField>,
         r_h: FpVar<C::ScalarField>,
         c: FpVar<C::ScalarField>,
@@ -71,9 +91,8 @@ where
         r: FpVar<C::ScalarField>,
         o_h: FpVar<C::ScalarField>,
     ) -> Result<(), SynthesisError> {
-        // cm == Commit(x) (Poseidon)
-        let computed_cm = CRHGadget::<C::ScalarField>::evaluate(crh_params, &[x.clone()]).unwrap();
-        computed_cm.enforce_equal(&cm)?;
+        // cm == Commit(x), delegated to the chosen CommitmentScheme
+        CS::check(commitment_params, crh_params, cs, &cm, &x)?;
 
         // r_h == HCommit(r, o_h) (Poseidon)
         let computed_r_h =
@@ -86,6 +105,151 @@ where
     }
 }
 
+/// GenZKCircuitWithChallenge is the same relation as [`GenZKCircuit`], but instead of taking the
+/// Fiat-Shamir challenge `c` as a trusted public input, it derives `c` itself in-circuit from
+/// `cm`, `R` and `r_h` using [`PoseidonTranscriptVar`]. This removes the need for the verifier to
+/// trust an externally supplied `c`: the verifier only needs to check that the `cm`, `R`, `r_h`
+/// public inputs match the ones used in the sigma check, and the circuit guarantees that `c` was
+/// derived honestly from them.
+#[derive(Debug, Clone)]
+pub struct GenZKCircuitWithChallenge<C: CurveGroup> {
+    pub poseidon_config: PoseidonConfig<C::ScalarField>,
+    // public
+    pub cm: C::ScalarField,
+    pub s: C::ScalarField,
+    pub r_h: C::ScalarField,
+    pub R: C,
+    // private
+    pub x: C::ScalarField,
+    pub r: C::ScalarField,
+    pub o_h: C::ScalarField,
+}
+impl<C: CurveGroup> ConstraintSynthesizer<CF<C>> for GenZKCircuitWithChallenge<C>
+where
+    C::ScalarField: Absorb,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF<C>>) -> Result<(), SynthesisError> {
+        // public inputs
+        let cmVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.cm))?;
+        let sVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.s))?;
+        let r_hVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.r_h))?;
+        // R only ever needs to feed the transcript below, so the public input allocated for it is
+        // its 2-element Fr reduction (see `transcript::prepare_point`), exactly the same values
+        // `PoseidonTranscript::absorb_point` feeds to the sponge natively -- not a non-native `R`
+        // itself, which would need curve-gadget machinery this circuit otherwise has no use for.
+        let reduced_R = crate::transcript::prepare_point(&self.R);
+        let r_xVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(reduced_R[0]))?;
+        let r_yVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(reduced_R[1]))?;
+
+        // private inputs
+        let xVar = FpVar::<C::ScalarField>::new_witness(cs.clone(), || Ok(self.x))?;
+        let rVar = FpVar::<C::ScalarField>::new_witness(cs.clone(), || Ok(self.r))?;
+        let o_hVar = FpVar::<C::ScalarField>::new_witness(cs.clone(), || Ok(self.o_h))?;
+
+        let crh_params =
+            CRHParametersVar::<C::ScalarField>::new_witness(
+                cs.clone(),
+                || Ok(self.poseidon_config.clone()),
+            )
+            .unwrap();
+
+        // cm == Commit(x) (Poseidon)
+        let computed_cm =
+            CRHGadget::<C::ScalarField>::evaluate(&crh_params, &[xVar.clone()]).unwrap();
+        computed_cm.enforce_equal(&cmVar)?;
+
+        // r_h == HCommit(r, o_h) (Poseidon)
+        let computed_r_h =
+            CRHGadget::<C::ScalarField>::evaluate(&crh_params, &[rVar.clone(), o_hVar.clone()])
+                .unwrap();
+        computed_r_h.enforce_equal(&r_hVar)?;
+
+        // derive c in-circuit, mirroring PoseidonTranscript::get_challenge over the same
+        // (cm, R, r_h) sequence absorbed natively by Sigmabus::prove/verify -- R enters only via
+        // its public reduction (r_xVar, r_yVar), matching absorb_point's own two-element
+        // absorption exactly.
+        let mut transcript = PoseidonTranscriptVar::<C>::new(cs.clone(), &self.poseidon_config);
+        transcript.absorb(&cmVar)?;
+        transcript.absorb(&r_xVar)?;
+        transcript.absorb(&r_yVar)?;
+        transcript.absorb(&r_hVar)?;
+        let cVar = transcript.get_challenge()?;
+
+        // s == r + c * x, against the in-circuit derived challenge
+        sVar.enforce_equal(&(rVar + (cVar * xVar)))?;
+
+        Ok(())
+    }
+}
+
+/// BatchedGenZKCircuit proves `n` independent instances of the [`GenZKCircuit`] relation in a
+/// single Groth16 proof.
+#[derive(Debug, Clone)]
+pub struct BatchedGenZKCircuit<C: CurveGroup, CS: CommitmentScheme<C>>
+where
+    C::ScalarField: Absorb,
+{
+    pub poseidon_config: PoseidonConfig<C::ScalarField>,
+    pub commitment_params: CS::Params,
+    // public, one entry per instance
+    pub cms: Vec<CS::Commitment>,
+    pub ss: Vec<C::ScalarField>,
+    pub r_hs: Vec<C::ScalarField>,
+    pub chals: Vec<C::ScalarField>,
+    // private, one entry per instance
+    pub xs: Vec<C::ScalarField>,
+    pub rs: Vec<C::ScalarField>,
+    pub o_hs: Vec<C::ScalarField>,
+}
+impl<C: CurveGroup, CS: CommitmentScheme<C>> ConstraintSynthesizer<CF<C>>
+    for BatchedGenZKCircuit<C, CS>
+where
+    C::ScalarField: Absorb,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF<C>>) -> Result<(), SynthesisError> {
+        let n = self.xs.len();
+        assert_eq!(self.cms.len(), n);
+        assert_eq!(self.ss.len(), n);
+        assert_eq!(self.r_hs.len(), n);
+        assert_eq!(self.chals.len(), n);
+        assert_eq!(self.rs.len(), n);
+        assert_eq!(self.o_hs.len(), n);
+
+        let crh_params = CRHParametersVar::<C::ScalarField>::new_witness(cs.clone(), || {
+            Ok(self.poseidon_config.clone())
+        })
+        .unwrap();
+
+        for i in 0..n {
+            // public inputs
+            let cmVar = CS::alloc_commitment(cs.clone(), &self.cms[i])?;
+            let sVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.ss[i]))?;
+            let r_hVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.r_hs[i]))?;
+            let cVar = FpVar::<C::ScalarField>::new_input(cs.clone(), || Ok(self.chals[i]))?;
+
+            // private inputs
+            let xVar = FpVar::<C::ScalarField>::new_witness(cs.clone(), || Ok(self.xs[i]))?;
+            let rVar = FpVar::<C::ScalarField>::new_witness(cs.clone(), || Ok(self.rs[i]))?;
+            let o_hVar = FpVar::<C::ScalarField>::new_witness(cs.clone(), || Ok(self.o_hs[i]))?;
+
+            GenZKCircuit::<C, CS>::check(
+                &self.commitment_params,
+                &crh_params,
+                cs.clone(),
+                cmVar,
+                sVar,
+                r_hVar,
+                cVar,
+                xVar,
+                rVar,
+                o_hVar,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
 // Note: since at the v0.4.0 of ark_curves the bn254 curve does not have the constraints
 // implemented, for the following tests we use the pallas curve.
 #[cfg(test)]
@@ -112,6 +276,7 @@ pub mod tests {
     use ark_r1cs_std::groups::curves::short_weierstrass::ProjectiveVar;
     pub type NonNativePallasGVar = ProjectiveVar<PallasConfig, NonNativeFieldVar<Fq, Fr>>;
 
+    use crate::commitment::PoseidonCommitment;
     use crate::sigmabus::SigmaProof;
     use crate::transcript::{tests::poseidon_test_config, PoseidonTranscript};
 
@@ -162,11 +327,14 @@ pub mod tests {
         let o_hVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(o_h)).unwrap();
 
         let crh_params =
-            CRHParametersVar::<Fr>::new_witness(cs.clone(), || Ok(poseidon_config)).unwrap();
+            CRHParametersVar::<Fr>::new_witness(cs.clone(), || Ok(poseidon_config.clone()))
+                .unwrap();
 
         // GenZK
-        GenZKCircuit::<Projective>::check(
+        GenZKCircuit::<Projective, PoseidonCommitment<Projective>>::check(
+            &poseidon_config,
             &crh_params,
+            cs.clone(),
             cmVar,
             sVar,
             r_hVar,
@@ -180,6 +348,119 @@ pub mod tests {
         dbg!(cs.num_constraints());
     }
 
+    #[test]
+    fn test_gen_zk_with_challenge_derivation() {
+        let mut rng = ark_std::test_rng();
+
+        let poseidon_config = poseidon_test_config::<Fr>();
+        let mut transcript = PoseidonTranscript::<Projective>::new(&poseidon_config);
+
+        let x = Fr::rand(&mut rng);
+
+        let mut sponge = PoseidonSponge::<Fr>::new(&poseidon_config);
+        sponge.absorb(&x);
+        let cm: Fr = sponge.squeeze_field_elements(1)[0];
+        transcript.absorb(&cm);
+
+        let r = Fr::rand(&mut rng);
+        let o_h = Fr::rand(&mut rng);
+
+        let R = Projective::generator().mul(r);
+
+        let mut sponge = PoseidonSponge::<Fr>::new(&poseidon_config);
+        sponge.absorb(&vec![r, o_h]);
+        let r_h: Fr = sponge.squeeze_field_elements(1)[0];
+
+        transcript.absorb_point(&R);
+        transcript.absorb(&r_h);
+        let c = transcript.get_challenge();
+
+        let s = r + c * x;
+
+        let circuit = GenZKCircuitWithChallenge::<Projective> {
+            poseidon_config,
+            cm,
+            s,
+            r_h,
+            R,
+            x,
+            r,
+            o_h,
+        };
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        dbg!(cs.num_constraints());
+    }
+
+    #[test]
+    fn test_batched_gen_zk() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_test_config::<Fr>();
+
+        let n = 4;
+        let mut transcript = PoseidonTranscript::<Projective>::new(&poseidon_config);
+
+        let mut cms = vec![];
+        let mut r_hs = vec![];
+        let mut xs = vec![];
+        let mut rs = vec![];
+        let mut o_hs = vec![];
+
+        for _ in 0..n {
+            let x = Fr::rand(&mut rng);
+
+            let mut sponge = PoseidonSponge::<Fr>::new(&poseidon_config);
+            sponge.absorb(&x);
+            let cm: Fr = sponge.squeeze_field_elements(1)[0];
+            transcript.absorb(&cm);
+
+            let r = Fr::rand(&mut rng);
+            let o_h = Fr::rand(&mut rng);
+
+            let R = Projective::generator().mul(r);
+
+            let mut sponge = PoseidonSponge::<Fr>::new(&poseidon_config);
+            sponge.absorb(&vec![r, o_h]);
+            let r_h: Fr = sponge.squeeze_field_elements(1)[0];
+
+            transcript.absorb_point(&R);
+            transcript.absorb(&r_h);
+
+            cms.push(cm);
+            xs.push(x);
+            rs.push(r);
+            o_hs.push(o_h);
+            r_hs.push(r_h);
+        }
+
+        let chals = transcript.get_challenge_vec(n);
+        let ss: Vec<Fr> = xs
+            .iter()
+            .zip(rs.iter())
+            .zip(chals.iter())
+            .map(|((x, r), c)| *r + *c * x)
+            .collect();
+
+        let circuit = BatchedGenZKCircuit::<Projective, PoseidonCommitment<Projective>> {
+            poseidon_config: poseidon_config.clone(),
+            commitment_params: poseidon_config,
+            cms,
+            ss,
+            r_hs,
+            chals,
+            xs,
+            rs,
+            o_hs,
+        };
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        dbg!(cs.num_constraints());
+    }
+
     // This circuit implements the x*G operation that Sigmabus proves, but here we do it in the
     // 'naive' way, which is computing it non-natively.
     struct NonNativeScalarMulCircuit<
@@ -0,0 +1,241 @@
+use ark_crypto_primitives::crh::{
+    poseidon::{
+        constraints::{CRHGadget, CRHParametersVar},
+        CRH,
+    },
+    CRHScheme, CRHSchemeGadget,
+};
+use ark_crypto_primitives::sponge::{poseidon::PoseidonConfig, Absorb};
+use ark_ec::{
+    short_weierstrass::{Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    bits::ToBitsGadget,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, nonnative::NonNativeFieldVar},
+    groups::{curves::short_weierstrass::ProjectiveVar, CurveVar, GroupOpsBounds},
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use core::marker::PhantomData;
+use std::ops::Mul;
+
+use crate::circuits::CF;
+use crate::transcript::PoseidonTranscript;
+
+/// CommitmentScheme abstracts the commitment to `x` used to compute `cm` in Sigmabus. Before this
+/// trait, `Sigmabus::prove` hard-coded `cm = Poseidon(x)` and `GenZKCircuit` enforced
+/// `cm == CRH(x)` directly. A homomorphic commitment (eg. Pedersen) lets a Sigmabus proof be
+/// linked to commitments produced by other protocols, since `cm` can then be checked for
+/// consistency against an externally supplied Pedersen/KZG opening instead of only being
+/// recomputed as Poseidon(x).
+pub trait CommitmentScheme<C: CurveGroup>
+where
+    C::ScalarField: Absorb,
+{
+    /// Public parameters of the commitment scheme (eg. the Poseidon config, or the Pedersen
+    /// generator).
+    type Params: Clone + core::fmt::Debug;
+    /// The commitment to `x`, as carried by `Proof::cm`.
+    type Commitment: Clone + Copy + core::fmt::Debug;
+    /// In-circuit representation of `Self::Commitment`, allocated inside `GenZKCircuit`.
+    type CommitmentVar: Clone + EqGadget<CF<C>>;
+
+    fn commit(params: &Self::Params, x: &C::ScalarField) -> Self::Commitment;
+
+    /// Absorbs `cm` into the native transcript, matching whatever representation
+    /// `PoseidonTranscriptVar` will use to absorb `Self::CommitmentVar` in-circuit.
+    fn absorb_commitment(transcript: &mut PoseidonTranscript<C>, cm: &Self::Commitment);
+
+    /// Flattens `cm` into the public-input field elements the Groth16 verifier is given, in the
+    /// same order `alloc_commitment` allocates them inside the circuit.
+    fn public_inputs(cm: &Self::Commitment) -> Vec<C::ScalarField>;
+
+    fn alloc_commitment(
+        cs: ConstraintSystemRef<CF<C>>,
+        cm: &Self::Commitment,
+    ) -> Result<Self::CommitmentVar, SynthesisError>;
+
+    /// Enforces `cm == Commit(x)` inside the constraint system. `crh_params` is passed along so
+    /// that schemes built on the same Poseidon CRH as the rest of `GenZKCircuit` (eg.
+    /// [`PoseidonCommitment`]) can reuse it instead of allocating their own copy.
+    fn check(
+        params: &Self::Params,
+        crh_params: &CRHParametersVar<C::ScalarField>,
+        cs: ConstraintSystemRef<CF<C>>,
+        cm: &Self::CommitmentVar,
+        x: &FpVar<C::ScalarField>,
+    ) -> Result<(), SynthesisError>;
+}
+
+/// PoseidonCommitment is the original Sigmabus commitment, `cm = Poseidon(x)`.
+#[derive(Debug, Clone)]
+pub struct PoseidonCommitment<C: CurveGroup> {
+    _c: PhantomData<C>,
+}
+
+impl<C: CurveGroup> CommitmentScheme<C> for PoseidonCommitment<C>
+where
+    C::ScalarField: Absorb,
+{
+    type Params = PoseidonConfig<C::ScalarField>;
+    type Commitment = C::ScalarField;
+    type CommitmentVar = FpVar<C::ScalarField>;
+
+    fn commit(params: &Self::Params, x: &C::ScalarField) -> Self::Commitment {
+        CRH::<C::ScalarField>::evaluate(params, [*x]).unwrap()
+    }
+
+    fn absorb_commitment(transcript: &mut PoseidonTranscript<C>, cm: &Self::Commitment) {
+        transcript.absorb(cm);
+    }
+
+    fn public_inputs(cm: &Self::Commitment) -> Vec<C::ScalarField> {
+        vec![*cm]
+    }
+
+    fn alloc_commitment(
+        cs: ConstraintSystemRef<CF<C>>,
+        cm: &Self::Commitment,
+    ) -> Result<Self::CommitmentVar, SynthesisError> {
+        FpVar::<C::ScalarField>::new_input(cs, || Ok(*cm))
+    }
+
+    fn check(
+        _params: &Self::Params,
+        crh_params: &CRHParametersVar<C::ScalarField>,
+        _cs: ConstraintSystemRef<CF<C>>,
+        cm: &Self::CommitmentVar,
+        x: &FpVar<C::ScalarField>,
+    ) -> Result<(), SynthesisError> {
+        let computed_cm = CRHGadget::<C::ScalarField>::evaluate(crh_params, &[x.clone()]).unwrap();
+        computed_cm.enforce_equal(cm)
+    }
+}
+
+/// Public parameters for [`PedersenCommitment`]: a single generator `H` of the curve, so that
+/// `Commit(x) = x·H`.
+#[derive(Debug, Clone)]
+pub struct PedersenParams<P: SWCurveConfig> {
+    pub generator: Projective<P>,
+}
+
+/// PedersenCommitment is a homomorphic commitment `cm = x·H` over a short Weierstrass curve `P`.
+#[derive(Debug, Clone)]
+pub struct PedersenCommitment<P: SWCurveConfig> {
+    _p: PhantomData<P>,
+}
+
+impl<P: SWCurveConfig> CommitmentScheme<Projective<P>> for PedersenCommitment<P>
+where
+    P::ScalarField: Absorb,
+    P::BaseField: PrimeField,
+{
+    type Params = PedersenParams<P>;
+    type Commitment = Projective<P>;
+    type CommitmentVar = ProjectiveVar<P, NonNativeFieldVar<P::BaseField, P::ScalarField>>;
+
+    fn commit(params: &Self::Params, x: &P::ScalarField) -> Self::Commitment {
+        params.generator.mul(*x)
+    }
+
+    fn absorb_commitment(
+        transcript: &mut PoseidonTranscript<Projective<P>>,
+        cm: &Self::Commitment,
+    ) {
+        transcript.absorb_point(cm);
+    }
+
+    fn public_inputs(cm: &Self::Commitment) -> Vec<P::ScalarField> {
+        crate::transcript::prepare_point(cm)
+    }
+
+    /// Allocates `cm` as a witness and binds it to its public `public_inputs` reduction.
+    fn alloc_commitment(
+        cs: ConstraintSystemRef<P::ScalarField>,
+        cm: &Self::Commitment,
+    ) -> Result<Self::CommitmentVar, SynthesisError> {
+        let cm_var = Self::CommitmentVar::new_witness(cs.clone(), || Ok(*cm))?;
+
+        let reduced = crate::transcript::prepare_point(cm);
+        let x_pub = FpVar::<P::ScalarField>::new_input(cs.clone(), || Ok(reduced[0]))?;
+        let y_pub = FpVar::<P::ScalarField>::new_input(cs.clone(), || Ok(reduced[1]))?;
+
+        Boolean::le_bits_to_fp_var(&cm_var.x.to_bits_le()?)?.enforce_equal(&x_pub)?;
+        Boolean::le_bits_to_fp_var(&cm_var.y.to_bits_le()?)?.enforce_equal(&y_pub)?;
+
+        Ok(cm_var)
+    }
+
+    fn check(
+        params: &Self::Params,
+        _crh_params: &CRHParametersVar<P::ScalarField>,
+        cs: ConstraintSystemRef<P::ScalarField>,
+        cm: &Self::CommitmentVar,
+        x: &FpVar<P::ScalarField>,
+    ) -> Result<(), SynthesisError> {
+        let generator_var = Self::CommitmentVar::new_constant(cs, params.generator)?;
+        let x_bits = x.to_bits_le()?;
+        let computed_cm = generator_var.scalar_mul_le(x_bits.iter())?;
+        computed_cm.enforce_equal(cm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{g1::Config as G1Config, Fr, G1Projective};
+    use ark_ec::Group;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    use crate::transcript::tests::poseidon_test_config;
+
+    #[test]
+    fn test_pedersen_commit_absorb_roundtrip() {
+        let mut rng = ark_std::test_rng();
+        let params = PedersenParams::<G1Config> {
+            generator: G1Projective::generator().mul(Fr::rand(&mut rng)),
+        };
+
+        let x = Fr::rand(&mut rng);
+        let cm = PedersenCommitment::<G1Config>::commit(&params, &x);
+        assert_eq!(cm, params.generator.mul(x));
+
+        let poseidon_config = poseidon_test_config::<Fr>();
+        let mut transcript = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+        PedersenCommitment::<G1Config>::absorb_commitment(&mut transcript, &cm);
+
+        let mut expected_transcript = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+        expected_transcript.absorb_point(&cm);
+        assert_eq!(
+            transcript.get_challenge(),
+            expected_transcript.get_challenge()
+        );
+    }
+
+    #[test]
+    fn test_pedersen_commitment_check_satisfied() {
+        let mut rng = ark_std::test_rng();
+        let params = PedersenParams::<G1Config> {
+            generator: G1Projective::generator().mul(Fr::rand(&mut rng)),
+        };
+        let x = Fr::rand(&mut rng);
+        let cm = PedersenCommitment::<G1Config>::commit(&params, &x);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let cm_var = PedersenCommitment::<G1Config>::alloc_commitment(cs.clone(), &cm).unwrap();
+        let x_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(x)).unwrap();
+
+        let poseidon_config = poseidon_test_config::<Fr>();
+        let crh_params =
+            CRHParametersVar::<Fr>::new_witness(cs.clone(), || Ok(poseidon_config)).unwrap();
+
+        PedersenCommitment::<G1Config>::check(&params, &crh_params, cs.clone(), &cm_var, &x_var)
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
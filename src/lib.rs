@@ -2,6 +2,7 @@
 #![allow(non_upper_case_globals)]
 /// Proof of concept implementation of [Sigmabus](https://eprint.iacr.org/2023/1406) as described in section 3 of the paper, using Groth16's zkSNARK scheme.
 pub mod circuits;
+pub mod commitment;
 pub mod sigmabus;
 pub mod transcript;
 
@@ -4,7 +4,18 @@ use ark_crypto_primitives::{
     sponge::{poseidon::PoseidonConfig, Absorb},
 };
 use ark_ec::{pairing::Pairing, CurveGroup, Group};
-use ark_groth16::{Groth16, Proof as Groth16Proof};
+use ark_groth16::{
+    constraints::{Groth16VerifierGadget, ProofVar, VerifyingKeyVar},
+    Groth16, Proof as Groth16Proof,
+};
+use ark_r1cs_std::{
+    bits::ToBitsGadget,
+    eq::EqGadget,
+    fields::fp::FpVar,
+    groups::{CurveVar, GroupOpsBounds},
+    pairing::PairingVar,
+};
+use ark_relations::r1cs::SynthesisError;
 use ark_std::{
     rand::{CryptoRng, Rng},
     UniformRand, Zero,
@@ -12,13 +23,14 @@ use ark_std::{
 use std::marker::PhantomData;
 use std::ops::Mul;
 
-use crate::circuits::GenZKCircuit;
+use crate::circuits::{BatchedGenZKCircuit, GenZKCircuit, GenZKCircuitWithChallenge};
+use crate::commitment::{CommitmentScheme, PoseidonCommitment};
 use crate::transcript::PoseidonTranscript;
 use crate::Error;
 
 /// Proof represents the Sigmabus proof
-pub struct Proof<E: Pairing> {
-    cm: E::ScalarField,
+pub struct Proof<E: Pairing, CS: CommitmentScheme<E::G1>> {
+    cm: CS::Commitment,
     sigma_proof: SigmaProof<E::G1>,
     zkproof: Groth16Proof<E>,
 }
@@ -30,7 +42,38 @@ pub struct SigmaProof<C: CurveGroup> {
     pub r_h: C::ScalarField,
 }
 
-pub struct Params<E: Pairing> {
+pub struct Params<E: Pairing, CS: CommitmentScheme<E::G1>> {
+    _e: PhantomData<E>,
+    poseidon_config: PoseidonConfig<E::ScalarField>,
+    commitment_params: CS::Params,
+    pk: <Groth16<E> as SNARK<E::ScalarField>>::ProvingKey,
+    vk: <Groth16<E> as SNARK<E::ScalarField>>::VerifyingKey,
+}
+
+/// BatchProof represents a Sigmabus proof for `n` independent `X_i = x_i·G` statements, batched
+/// into a single Groth16 proof (see [`BatchedGenZKCircuit`]).
+pub struct BatchProof<E: Pairing, CS: CommitmentScheme<E::G1>> {
+    cms: Vec<CS::Commitment>,
+    sigma_proofs: Vec<SigmaProof<E::G1>>,
+    zkproof: Groth16Proof<E>,
+}
+
+/// BatchParams holds the trusted setup for a batch of a fixed size `n`, since the
+/// [`BatchedGenZKCircuit`] shape (and thus the Groth16 proving/verifying keys) depends on `n`.
+pub struct BatchParams<E: Pairing, CS: CommitmentScheme<E::G1>> {
+    _e: PhantomData<E>,
+    poseidon_config: PoseidonConfig<E::ScalarField>,
+    commitment_params: CS::Params,
+    n: usize,
+    pk: <Groth16<E> as SNARK<E::ScalarField>>::ProvingKey,
+    vk: <Groth16<E> as SNARK<E::ScalarField>>::VerifyingKey,
+}
+
+/// ParamsWithChallenge holds the trusted setup for the challenge-derivation variant of Sigmabus
+/// (see [`GenZKCircuitWithChallenge`]), proven with [`Sigmabus::prove_with_challenge_derivation`]
+/// and checked with [`Sigmabus::verify_with_challenge_derivation`]. Only defined for
+/// [`PoseidonCommitment`], since [`GenZKCircuitWithChallenge`] hard-codes `cm = Poseidon(x)`.
+pub struct ParamsWithChallenge<E: Pairing> {
     _e: PhantomData<E>,
     poseidon_config: PoseidonConfig<E::ScalarField>,
     pk: <Groth16<E> as SNARK<E::ScalarField>>::ProvingKey,
@@ -38,24 +81,32 @@ pub struct Params<E: Pairing> {
 }
 
 /// Sigmabus implements [Sigmabus](https://eprint.iacr.org/2023/1406) prover & verifier for proving
-/// X=x*G as described in section 3 of the paper, using Groth16's zkSNARK scheme.
-pub struct Sigmabus<E: Pairing> {
+/// X=x*P as described in section 3 of the paper (P is typically the curve's generator G, but
+/// `prove`/`verify` take any public base point, eg. for Diffie-Hellman / key-blinding
+/// statements), using Groth16's zkSNARK scheme. It is generic over the [`CommitmentScheme`] used
+/// to compute `cm`, so that a homomorphic commitment (eg. Pedersen) can be plugged in instead of
+/// the default Poseidon digest.
+pub struct Sigmabus<E: Pairing, CS: CommitmentScheme<E::G1>> {
     _e: PhantomData<E>,
+    _cs: PhantomData<CS>,
 }
 
-impl<E: Pairing> Sigmabus<E>
+impl<E: Pairing, CS: CommitmentScheme<E::G1>> Sigmabus<E, CS>
 where
     E::ScalarField: Absorb,
 {
     pub fn setup<R: Rng + CryptoRng>(
         rng: &mut R,
         poseidon_config: &PoseidonConfig<E::ScalarField>,
-    ) -> Params<E> {
-        let circuit = GenZKCircuit::<E::G1> {
+        commitment_params: &CS::Params,
+    ) -> Params<E, CS> {
+        let circuit = GenZKCircuit::<E::G1, CS> {
             _c: PhantomData,
             poseidon_config: poseidon_config.clone(),
-            // public
-            cm: E::ScalarField::zero(),
+            commitment_params: commitment_params.clone(),
+            // public (cm commits to a non-zero dummy, as a homomorphic commitment to zero would be
+            // the point at infinity, which `transcript::prepare_point` cannot reduce)
+            cm: CS::commit(commitment_params, &E::ScalarField::from(1u64)),
             s: E::ScalarField::zero(),
             r_h: E::ScalarField::zero(),
             c: E::ScalarField::zero(),
@@ -67,29 +118,36 @@ where
 
         // generate the snark proof
         let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), rng).unwrap();
-        Params::<E> {
+        Params::<E, CS> {
             _e: PhantomData,
             poseidon_config: poseidon_config.clone(),
+            commitment_params: commitment_params.clone(),
             pk,
             vk,
         }
     }
 
+    /// Proves `X = x·P` for the given public base point `P` (eg. `E::G1::generator()` for the
+    /// original Sigmabus relation, or any other protocol-specific base). `P` is absorbed into the
+    /// transcript before `cm`, so that a proof bound to one base cannot be replayed against
+    /// another.
     pub fn prove<R: Rng + CryptoRng>(
         rng: &mut R,
-        params: &Params<E>,
+        params: &Params<E, CS>,
         transcript: &mut PoseidonTranscript<E::G1>,
+        P: E::G1,
         x: E::ScalarField,
-    ) -> Result<Proof<E>, Error> {
+    ) -> Result<Proof<E, CS>, Error> {
+        transcript.absorb_point(&P);
+
         // cm
-        let cm: E::ScalarField =
-            CRH::<E::ScalarField>::evaluate(&params.poseidon_config, [x]).unwrap();
-        transcript.absorb(&cm);
+        let cm = CS::commit(&params.commitment_params, &x);
+        CS::absorb_commitment(transcript, &cm);
 
         let r = E::ScalarField::rand(rng);
         let o_h = E::ScalarField::rand(rng);
 
-        let R = E::G1::generator().mul(r);
+        let R = P.mul(r);
 
         let r_h: E::ScalarField =
             CRH::<E::ScalarField>::evaluate(&params.poseidon_config, [r, o_h]).unwrap();
@@ -101,9 +159,10 @@ where
 
         let s = r + c * x;
 
-        let circuit = GenZKCircuit::<E::G1> {
+        let circuit = GenZKCircuit::<E::G1, CS> {
             _c: PhantomData,
             poseidon_config: params.poseidon_config.clone(),
+            commitment_params: params.commitment_params.clone(),
             // public
             cm,
             s,
@@ -125,15 +184,19 @@ where
         })
     }
 
+    /// Verifies a [`Proof`] for `X = x·P` against the same base point `P` used in `prove`.
     pub fn verify(
-        params: &Params<E>,
+        params: &Params<E, CS>,
         transcript: &mut PoseidonTranscript<E::G1>,
-        proof: Proof<E>,
+        P: E::G1,
+        proof: Proof<E, CS>,
         X: E::G1,
     ) -> Result<(), Error> {
-        let lhs = E::G1::generator().mul(proof.sigma_proof.s);
+        transcript.absorb_point(&P);
 
-        transcript.absorb(&proof.cm);
+        let lhs = P.mul(proof.sigma_proof.s);
+
+        CS::absorb_commitment(transcript, &proof.cm);
         transcript.absorb_point(&proof.sigma_proof.R);
         transcript.absorb(&proof.sigma_proof.r_h);
         let c = transcript.get_challenge();
@@ -145,9 +208,281 @@ where
         }
 
         // verify zkSNARK proof
-        let public_input = [proof.cm, proof.sigma_proof.s, proof.sigma_proof.r_h, c];
+        let mut public_input = CS::public_inputs(&proof.cm);
+        public_input.push(proof.sigma_proof.s);
+        public_input.push(proof.sigma_proof.r_h);
+        public_input.push(c);
+
+        let valid_proof =
+            Groth16::<E>::verify(&params.vk, &public_input, &proof.zkproof).unwrap();
+        if !valid_proof {
+            return Err(Error::GenZKFail);
+        }
+
+        Ok(())
+    }
+
+    /// Generates the trusted setup for a batch of `n` instances, proven together in a single
+    /// Groth16 proof via [`BatchedGenZKCircuit`].
+    pub fn setup_batch<R: Rng + CryptoRng>(
+        rng: &mut R,
+        poseidon_config: &PoseidonConfig<E::ScalarField>,
+        commitment_params: &CS::Params,
+        n: usize,
+    ) -> BatchParams<E, CS> {
+        let circuit = BatchedGenZKCircuit::<E::G1, CS> {
+            poseidon_config: poseidon_config.clone(),
+            commitment_params: commitment_params.clone(),
+            cms: vec![CS::commit(commitment_params, &E::ScalarField::from(1u64)); n],
+            ss: vec![E::ScalarField::zero(); n],
+            r_hs: vec![E::ScalarField::zero(); n],
+            chals: vec![E::ScalarField::zero(); n],
+            xs: vec![E::ScalarField::zero(); n],
+            rs: vec![E::ScalarField::zero(); n],
+            o_hs: vec![E::ScalarField::zero(); n],
+        };
+
+        let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), rng).unwrap();
+        BatchParams::<E, CS> {
+            _e: PhantomData,
+            poseidon_config: poseidon_config.clone(),
+            commitment_params: commitment_params.clone(),
+            n,
+            pk,
+            vk,
+        }
+    }
+
+    /// Proves `n = xs.len()` statements `X_i = x_i·G` in a single Groth16 proof, amortizing the
+    /// proving cost over the batch instead of producing one [`Proof`] per instance.
+    pub fn prove_batch<R: Rng + CryptoRng>(
+        rng: &mut R,
+        params: &BatchParams<E, CS>,
+        transcript: &mut PoseidonTranscript<E::G1>,
+        xs: &[E::ScalarField],
+    ) -> Result<BatchProof<E, CS>, Error> {
+        let n = xs.len();
+        assert_eq!(n, params.n);
+
+        let mut cms = Vec::with_capacity(n);
+        let mut Rs = Vec::with_capacity(n);
+        let mut r_hs = Vec::with_capacity(n);
+        let mut rs = Vec::with_capacity(n);
+        let mut o_hs = Vec::with_capacity(n);
+
+        for &x in xs {
+            let cm = CS::commit(&params.commitment_params, &x);
+            CS::absorb_commitment(transcript, &cm);
+
+            let r = E::ScalarField::rand(rng);
+            let o_h = E::ScalarField::rand(rng);
+            let R = E::G1::generator().mul(r);
+            let r_h: E::ScalarField =
+                CRH::<E::ScalarField>::evaluate(&params.poseidon_config, [r, o_h]).unwrap();
+
+            transcript.absorb_point(&R);
+            transcript.absorb(&r_h);
+
+            cms.push(cm);
+            Rs.push(R);
+            r_hs.push(r_h);
+            rs.push(r);
+            o_hs.push(o_h);
+        }
+
+        let chals = transcript.get_challenge_vec(n);
+        let ss: Vec<E::ScalarField> = xs
+            .iter()
+            .zip(rs.iter())
+            .zip(chals.iter())
+            .map(|((x, r), c)| *r + *c * x)
+            .collect();
+
+        let circuit = BatchedGenZKCircuit::<E::G1, CS> {
+            poseidon_config: params.poseidon_config.clone(),
+            commitment_params: params.commitment_params.clone(),
+            cms: cms.clone(),
+            ss: ss.clone(),
+            r_hs: r_hs.clone(),
+            chals: chals.clone(),
+            xs: xs.to_vec(),
+            rs: rs.clone(),
+            o_hs,
+        };
+
+        let zkproof = Groth16::<E>::prove(&params.pk, circuit.clone(), rng).unwrap();
+
+        let sigma_proofs = (0..n)
+            .map(|i| SigmaProof {
+                s: ss[i],
+                R: Rs[i],
+                r_h: r_hs[i],
+            })
+            .collect();
+
+        Ok(BatchProof {
+            cms,
+            sigma_proofs,
+            zkproof,
+        })
+    }
+
+    /// Verifies a [`BatchProof`] for `Xs[i] = xs[i]·G`, for all `i` in the batch.
+    pub fn verify_batch(
+        params: &BatchParams<E, CS>,
+        transcript: &mut PoseidonTranscript<E::G1>,
+        proof: BatchProof<E, CS>,
+        Xs: &[E::G1],
+    ) -> Result<(), Error> {
+        let n = Xs.len();
+        if proof.cms.len() != n || proof.sigma_proofs.len() != n || n != params.n {
+            return Err(Error::SigmaFail);
+        }
+
+        // same per-instance (cm, R, r_h) absorption order as prove_batch
+        for (cm, sigma_proof) in proof.cms.iter().zip(proof.sigma_proofs.iter()) {
+            CS::absorb_commitment(transcript, cm);
+            transcript.absorb_point(&sigma_proof.R);
+            transcript.absorb(&sigma_proof.r_h);
+        }
+        let chals = transcript.get_challenge_vec(n);
+
+        let mut public_input = Vec::new();
+        for i in 0..n {
+            let lhs = E::G1::generator().mul(proof.sigma_proofs[i].s);
+            let rhs = proof.sigma_proofs[i].R + Xs[i].mul(chals[i]);
+            if lhs != rhs {
+                return Err(Error::SigmaFail);
+            }
+
+            public_input.extend(CS::public_inputs(&proof.cms[i]));
+            public_input.push(proof.sigma_proofs[i].s);
+            public_input.push(proof.sigma_proofs[i].r_h);
+            public_input.push(chals[i]);
+        }
+
+        let valid_proof =
+            Groth16::<E>::verify(&params.vk, &public_input, &proof.zkproof).unwrap();
+        if !valid_proof {
+            return Err(Error::GenZKFail);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Pairing> Sigmabus<E, PoseidonCommitment<E::G1>>
+where
+    E::ScalarField: Absorb,
+{
+    /// Generates the trusted setup for [`Sigmabus::prove_with_challenge_derivation`] /
+    /// [`Sigmabus::verify_with_challenge_derivation`], which use [`GenZKCircuitWithChallenge`]
+    /// instead of [`GenZKCircuit`].
+    pub fn setup_with_challenge_derivation<R: Rng + CryptoRng>(
+        rng: &mut R,
+        poseidon_config: &PoseidonConfig<E::ScalarField>,
+    ) -> ParamsWithChallenge<E> {
+        let circuit = GenZKCircuitWithChallenge::<E::G1> {
+            poseidon_config: poseidon_config.clone(),
+            cm: E::ScalarField::zero(),
+            s: E::ScalarField::zero(),
+            r_h: E::ScalarField::zero(),
+            // R must not be the point at infinity, which `transcript::prepare_point` cannot reduce
+            R: E::G1::generator(),
+            x: E::ScalarField::zero(),
+            r: E::ScalarField::zero(),
+            o_h: E::ScalarField::zero(),
+        };
+
+        let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit, rng).unwrap();
+        ParamsWithChallenge::<E> {
+            _e: PhantomData,
+            poseidon_config: poseidon_config.clone(),
+            pk,
+            vk,
+        }
+    }
+
+    /// Proves `X = x·P` via [`GenZKCircuitWithChallenge`]: unlike [`Sigmabus::prove`], the
+    /// Fiat-Shamir challenge `c` is not part of the Groth16 public input the verifier supplies --
+    /// the circuit re-derives it itself from `cm`, `R`, `r_h`, the same values
+    /// [`Sigmabus::verify_with_challenge_derivation`] already re-derives `c` from for the native
+    /// sigma check, so there is no externally-supplied `c` left for the verifier to trust.
+    pub fn prove_with_challenge_derivation<R: Rng + CryptoRng>(
+        rng: &mut R,
+        params: &ParamsWithChallenge<E>,
+        transcript: &mut PoseidonTranscript<E::G1>,
+        P: E::G1,
+        x: E::ScalarField,
+    ) -> Result<Proof<E, PoseidonCommitment<E::G1>>, Error> {
+        let cm: E::ScalarField =
+            CRH::<E::ScalarField>::evaluate(&params.poseidon_config, [x]).unwrap();
+        transcript.absorb(&cm);
+
+        let r = E::ScalarField::rand(rng);
+        let o_h = E::ScalarField::rand(rng);
+
+        let R = P.mul(r);
+
+        let r_h: E::ScalarField =
+            CRH::<E::ScalarField>::evaluate(&params.poseidon_config, [r, o_h]).unwrap();
+
+        transcript.absorb_point(&R);
+        transcript.absorb(&r_h);
+
+        let c = transcript.get_challenge();
+        let s = r + c * x;
+
+        let circuit = GenZKCircuitWithChallenge::<E::G1> {
+            poseidon_config: params.poseidon_config.clone(),
+            cm,
+            s,
+            r_h,
+            R,
+            x,
+            r,
+            o_h,
+        };
+
+        let zkproof = Groth16::<E>::prove(&params.pk, circuit, rng).unwrap();
+
+        Ok(Proof {
+            cm,
+            sigma_proof: SigmaProof { s, R, r_h },
+            zkproof,
+        })
+    }
+
+    /// Verifies a [`Proof`] produced by [`Sigmabus::prove_with_challenge_derivation`]. The native
+    /// sigma equation `s·P == R + c·X` still needs `c`, since that check happens outside the
+    /// SNARK, but the Groth16 public input passed to the verifier no longer includes `c` at all --
+    /// only `cm`, `s`, `r_h` and `R`'s reduction, matching
+    /// `GenZKCircuitWithChallenge::generate_constraints`'s allocation order, so the circuit's own
+    /// in-circuit derivation of `c` is what the Groth16 relation is checked against.
+    pub fn verify_with_challenge_derivation(
+        params: &ParamsWithChallenge<E>,
+        transcript: &mut PoseidonTranscript<E::G1>,
+        P: E::G1,
+        proof: Proof<E, PoseidonCommitment<E::G1>>,
+        X: E::G1,
+    ) -> Result<(), Error> {
+        let lhs = P.mul(proof.sigma_proof.s);
+
+        transcript.absorb(&proof.cm);
+        transcript.absorb_point(&proof.sigma_proof.R);
+        transcript.absorb(&proof.sigma_proof.r_h);
+        let c = transcript.get_challenge();
 
-        let valid_proof = Groth16::<E>::verify(&params.vk, &public_input, &proof.zkproof).unwrap();
+        let rhs = proof.sigma_proof.R + X.mul(c);
+        if lhs != rhs {
+            return Err(Error::SigmaFail);
+        }
+
+        let mut public_input = vec![proof.cm, proof.sigma_proof.s, proof.sigma_proof.r_h];
+        public_input.extend(crate::transcript::prepare_point(&proof.sigma_proof.R));
+
+        let valid_proof =
+            Groth16::<E>::verify(&params.vk, &public_input, &proof.zkproof).unwrap();
         if !valid_proof {
             return Err(Error::GenZKFail);
         }
@@ -156,6 +491,68 @@ where
     }
 }
 
+/// SigmaProofVar is the in-circuit representation of a [`SigmaProof`] over `E::G1`.
+pub struct SigmaProofVar<C: CurveGroup, GC: CurveVar<C, C::ScalarField>> {
+    pub s: FpVar<C::ScalarField>,
+    pub R: GC,
+    pub r_h: FpVar<C::ScalarField>,
+}
+
+/// SigmabusVerifyGadget enforces, inside an outer circuit, that a Sigmabus [`Proof`] is valid for
+/// the statement `X = x·P`.
+pub struct SigmabusVerifyGadget<E: Pairing, GC: CurveVar<E::G1, E::ScalarField>>
+where
+    for<'a> &'a GC: GroupOpsBounds<'a, E::G1, GC>,
+{
+    _e: PhantomData<E>,
+    _gc: PhantomData<GC>,
+}
+
+impl<E: Pairing, GC: CurveVar<E::G1, E::ScalarField>> SigmabusVerifyGadget<E, GC>
+where
+    for<'a> &'a GC: GroupOpsBounds<'a, E::G1, GC>,
+{
+    /// Enforces the sigma check `s·P == R + c·X` in-circuit.
+    pub fn check_sigma(
+        P: &GC,
+        X: &GC,
+        sigma_proof: &SigmaProofVar<E::G1, GC>,
+        c: &FpVar<E::ScalarField>,
+    ) -> Result<(), SynthesisError> {
+        let lhs = P.scalar_mul_le(sigma_proof.s.to_bits_le()?.iter())?;
+        let cX = X.scalar_mul_le(c.to_bits_le()?.iter())?;
+        let rhs = sigma_proof.R.clone() + cX;
+        lhs.enforce_equal(&rhs)
+    }
+
+    /// Enforces the Groth16 verification of the `GenZKCircuit` zkSNARK proof in-circuit.
+    pub fn check_zkproof<PG: PairingVar<E, E::ScalarField>>(
+        vk: &VerifyingKeyVar<E, PG>,
+        public_input: &[FpVar<E::ScalarField>],
+        zkproof: &ProofVar<E, PG>,
+    ) -> Result<(), SynthesisError> {
+        let valid = Groth16VerifierGadget::<E, PG>::verify(vk, public_input, zkproof)?;
+        valid.enforce_equal(&ark_r1cs_std::boolean::Boolean::TRUE)
+    }
+
+    /// Runs both `check_sigma` and `check_zkproof`. Unlike `Sigmabus::verify`, it does not derive
+    /// `c` from a transcript and does not check that `public_input` holds the proof's
+    /// `(cm, s, r_h, c)`: both `c` and `public_input` are taken as given by the caller.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify<PG: PairingVar<E, E::ScalarField>>(
+        P: &GC,
+        X: &GC,
+        sigma_proof: &SigmaProofVar<E::G1, GC>,
+        c: &FpVar<E::ScalarField>,
+        vk: &VerifyingKeyVar<E, PG>,
+        public_input: &[FpVar<E::ScalarField>],
+        zkproof: &ProofVar<E, PG>,
+    ) -> Result<(), SynthesisError> {
+        Self::check_sigma(P, X, sigma_proof, c)?;
+        Self::check_zkproof(vk, public_input, zkproof)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,6 +560,7 @@ mod tests {
     use ark_std::rand::{RngCore, SeedableRng};
     use ark_std::test_rng;
 
+    use crate::commitment::{PedersenCommitment, PedersenParams, PoseidonCommitment};
     use crate::transcript::tests::poseidon_test_config;
 
     #[test]
@@ -171,7 +569,11 @@ mod tests {
         let poseidon_config = poseidon_test_config::<Fr>();
 
         // generate the trusted setup
-        let params = Sigmabus::<Bn254>::setup(&mut rng, &poseidon_config);
+        let params = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::setup(
+            &mut rng,
+            &poseidon_config,
+            &poseidon_config,
+        );
 
         // compute X = x * G
         let x = Fr::rand(&mut rng);
@@ -180,10 +582,297 @@ mod tests {
         let mut transcript_p = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
 
         // generate Sigmabus proof for X==x*G
-        let proof = Sigmabus::<Bn254>::prove(&mut rng, &params, &mut transcript_p, x).unwrap();
+        let G = G1Projective::generator();
+        let proof =
+            Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::prove(
+                &mut rng,
+                &params,
+                &mut transcript_p,
+                G,
+                x,
+            )
+            .unwrap();
+
+        // verify Sigmabus proof for X==x*G
+        let mut transcript_v = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+        Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::verify(
+            &params,
+            &mut transcript_v,
+            G,
+            proof,
+            X,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sigmabus_prove_verify_arbitrary_base() {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let poseidon_config = poseidon_test_config::<Fr>();
+
+        // generate the trusted setup
+        let params = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::setup(
+            &mut rng,
+            &poseidon_config,
+            &poseidon_config,
+        );
+
+        // use a base point P other than the generator
+        let P = G1Projective::generator().mul(Fr::rand(&mut rng));
+
+        // compute X = x * P
+        let x = Fr::rand(&mut rng);
+        let X = P.mul(x);
+
+        let mut transcript_p = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+
+        // generate Sigmabus proof for X==x*P
+        let proof = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::prove(
+            &mut rng,
+            &params,
+            &mut transcript_p,
+            P,
+            x,
+        )
+        .unwrap();
+
+        // verify Sigmabus proof for X==x*P
+        let mut transcript_v = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+        Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::verify(
+            &params,
+            &mut transcript_v,
+            P,
+            proof,
+            X,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sigmabus_verify_gadget_satisfied() {
+        // SigmabusVerifyGadget needs a real pairing gadget for check_zkproof, which bn254 does
+        // not have at this ark_curves version (see the note in circuits.rs) -- bls12_377 does, so
+        // we use it here, same as the rest of the ecosystem does for recursive Groth16
+        // verification. R is represented non-natively via GC, analogous to NonNativePallasGVar.
+        use ark_bls12_377::{
+            constraints::PairingVar as Bls12_377PairingVar, g1::Config as Bls12_377G1Config,
+            Bls12_377, Fq as Bls12_377Fq, Fr as Bls12_377Fr, G1Projective as Bls12_377G1,
+        };
+        use ark_groth16::constraints::{ProofVar, VerifyingKeyVar};
+        use ark_r1cs_std::{
+            fields::nonnative::NonNativeFieldVar,
+            groups::curves::short_weierstrass::ProjectiveVar,
+        };
+        use ark_relations::r1cs::ConstraintSystem;
+
+        type GC = ProjectiveVar<Bls12_377G1Config, NonNativeFieldVar<Bls12_377Fq, Bls12_377Fr>>;
+
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let poseidon_config = poseidon_test_config::<Bls12_377Fr>();
+
+        let params = Sigmabus::<Bls12_377, PoseidonCommitment<Bls12_377G1>>::setup(
+            &mut rng,
+            &poseidon_config,
+            &poseidon_config,
+        );
+
+        let x = Bls12_377Fr::rand(&mut rng);
+        let G = Bls12_377G1::generator();
+        let X = G.mul(x);
+
+        let mut transcript_p = PoseidonTranscript::<Bls12_377G1>::new(&poseidon_config);
+        let proof = Sigmabus::<Bls12_377, PoseidonCommitment<Bls12_377G1>>::prove(
+            &mut rng,
+            &params,
+            &mut transcript_p,
+            G,
+            x,
+        )
+        .unwrap();
+
+        // recompute c and the Groth16 public input exactly as Sigmabus::verify does natively, so
+        // the gadget is fed the same values the native verifier would check against.
+        let mut transcript_v = PoseidonTranscript::<Bls12_377G1>::new(&poseidon_config);
+        transcript_v.absorb_point(&G);
+        PoseidonCommitment::<Bls12_377G1>::absorb_commitment(&mut transcript_v, &proof.cm);
+        transcript_v.absorb_point(&proof.sigma_proof.R);
+        transcript_v.absorb(&proof.sigma_proof.r_h);
+        let c = transcript_v.get_challenge();
+
+        let mut public_input = PoseidonCommitment::<Bls12_377G1>::public_inputs(&proof.cm);
+        public_input.push(proof.sigma_proof.s);
+        public_input.push(proof.sigma_proof.r_h);
+        public_input.push(c);
+
+        let cs = ConstraintSystem::<Bls12_377Fr>::new_ref();
+
+        let G_var = GC::new_constant(cs.clone(), G).unwrap();
+        let X_var = GC::new_input(cs.clone(), || Ok(X)).unwrap();
+        let R_var = GC::new_witness(cs.clone(), || Ok(proof.sigma_proof.R)).unwrap();
+        let s_var =
+            FpVar::<Bls12_377Fr>::new_witness(cs.clone(), || Ok(proof.sigma_proof.s)).unwrap();
+        let r_h_var =
+            FpVar::<Bls12_377Fr>::new_witness(cs.clone(), || Ok(proof.sigma_proof.r_h)).unwrap();
+        let c_var = FpVar::<Bls12_377Fr>::new_witness(cs.clone(), || Ok(c)).unwrap();
+        let sigma_proof_var = SigmaProofVar {
+            s: s_var,
+            R: R_var,
+            r_h: r_h_var,
+        };
+
+        let vk_var =
+            VerifyingKeyVar::<Bls12_377, Bls12_377PairingVar>::new_witness(cs.clone(), || {
+                Ok(params.vk.clone())
+            })
+            .unwrap();
+        let zkproof_var = ProofVar::<Bls12_377, Bls12_377PairingVar>::new_witness(cs.clone(), || {
+            Ok(proof.zkproof.clone())
+        })
+        .unwrap();
+        let public_input_vars: Vec<FpVar<Bls12_377Fr>> = public_input
+            .iter()
+            .map(|v| FpVar::<Bls12_377Fr>::new_input(cs.clone(), || Ok(*v)).unwrap())
+            .collect();
+
+        SigmabusVerifyGadget::<Bls12_377, GC>::verify::<Bls12_377PairingVar>(
+            &G_var,
+            &X_var,
+            &sigma_proof_var,
+            &c_var,
+            &vk_var,
+            &public_input_vars,
+            &zkproof_var,
+        )
+        .unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_sigmabus_prove_verify_with_challenge_derivation() {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let poseidon_config = poseidon_test_config::<Fr>();
+
+        // generate the trusted setup
+        let params =
+            Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::setup_with_challenge_derivation(
+                &mut rng,
+                &poseidon_config,
+            );
+
+        // compute X = x * G
+        let x = Fr::rand(&mut rng);
+        let G = G1Projective::generator();
+        let X = G.mul(x);
+
+        let mut transcript_p = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+
+        // generate a Sigmabus proof for X==x*G whose Groth16 public input does not include `c`
+        let proof = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::prove_with_challenge_derivation(
+            &mut rng,
+            &params,
+            &mut transcript_p,
+            G,
+            x,
+        )
+        .unwrap();
+
+        // verify it
+        let mut transcript_v = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+        Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::verify_with_challenge_derivation(
+            &params,
+            &mut transcript_v,
+            G,
+            proof,
+            X,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sigmabus_prove_verify_pedersen() {
+        // exercises Sigmabus end-to-end with a homomorphic PedersenCommitment instead of the
+        // default PoseidonCommitment, over bn254's G1 curve config.
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let poseidon_config = poseidon_test_config::<Fr>();
+
+        let pedersen_params = PedersenParams::<ark_bn254::g1::Config> {
+            generator: G1Projective::generator().mul(Fr::rand(&mut rng)),
+        };
+
+        // generate the trusted setup
+        let params = Sigmabus::<Bn254, PedersenCommitment<ark_bn254::g1::Config>>::setup(
+            &mut rng,
+            &poseidon_config,
+            &pedersen_params,
+        );
+
+        // compute X = x * G
+        let x = Fr::rand(&mut rng);
+        let G = G1Projective::generator();
+        let X = G.mul(x);
+
+        let mut transcript_p = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+
+        // generate Sigmabus proof for X==x*G
+        let proof = Sigmabus::<Bn254, PedersenCommitment<ark_bn254::g1::Config>>::prove(
+            &mut rng,
+            &params,
+            &mut transcript_p,
+            G,
+            x,
+        )
+        .unwrap();
 
         // verify Sigmabus proof for X==x*G
         let mut transcript_v = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
-        Sigmabus::<Bn254>::verify(&params, &mut transcript_v, proof, X).unwrap();
+        Sigmabus::<Bn254, PedersenCommitment<ark_bn254::g1::Config>>::verify(
+            &params,
+            &mut transcript_v,
+            G,
+            proof,
+            X,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sigmabus_prove_verify_batch() {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let poseidon_config = poseidon_test_config::<Fr>();
+        let n = 4;
+
+        // generate the trusted setup for a batch of n instances
+        let params = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::setup_batch(
+            &mut rng,
+            &poseidon_config,
+            &poseidon_config,
+            n,
+        );
+
+        // compute Xs = xs * G
+        let xs: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let Xs: Vec<G1Projective> = xs.iter().map(|x| G1Projective::generator().mul(x)).collect();
+
+        let mut transcript_p = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+
+        // generate a single Sigmabus proof for all Xs[i] == xs[i] * G
+        let proof = Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::prove_batch(
+            &mut rng,
+            &params,
+            &mut transcript_p,
+            &xs,
+        )
+        .unwrap();
+
+        // verify the batched Sigmabus proof
+        let mut transcript_v = PoseidonTranscript::<G1Projective>::new(&poseidon_config);
+        Sigmabus::<Bn254, PoseidonCommitment<G1Projective>>::verify_batch(
+            &params,
+            &mut transcript_v,
+            proof,
+            &Xs,
+        )
+        .unwrap();
     }
 }